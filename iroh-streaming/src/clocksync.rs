@@ -0,0 +1,151 @@
+//! NTP-style peer clock synchronization.
+//!
+//! Every neighbor runs its own independent wall clock, so a remote
+//! `MediaChunk.timestamp` can't be compared directly to local time. This
+//! module estimates the offset (and round-trip delay) between our clock
+//! and each neighbor's using the classic four-timestamp NTP exchange, so
+//! callers can translate a remote timestamp into a local playout deadline.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+/// How many recent samples to keep per neighbor before trimming the oldest.
+const HISTORY_LEN: usize = 8;
+
+/// Clock-sync messages exchanged over the existing signaling channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClockSyncMessage {
+    /// Sent by the requester, stamping `t1` (its local send time).
+    Probe { requester: String, t1: u64 },
+    /// Echoed back by the responder with its own receive (`t2`) and send (`t3`) times.
+    Response {
+        requester: String,
+        t1: u64,
+        t2: u64,
+        t3: u64,
+    },
+}
+
+/// One offset/RTT estimate derived from a single NTP-style exchange.
+#[derive(Debug, Clone, Copy)]
+struct ClockSample {
+    offset_ms: f64,
+    rtt_ms: f64,
+}
+
+/// Offset = ((t2 − t1) + (t3 − t4)) / 2, RTT = (t4 − t1) − (t3 − t2).
+pub fn compute_sample(t1: u64, t2: u64, t3: u64, t4: u64) -> (f64, f64) {
+    let (t1, t2, t3, t4) = (t1 as i64, t2 as i64, t3 as i64, t4 as i64);
+    let offset = ((t2 - t1) + (t3 - t4)) as f64 / 2.0;
+    let rtt = ((t4 - t1) - (t3 - t2)) as f64;
+    (offset, rtt)
+}
+
+/// Recent offset/RTT history for one neighbor.
+#[derive(Default)]
+struct NeighborClock {
+    samples: VecDeque<ClockSample>,
+}
+
+impl NeighborClock {
+    fn record(&mut self, offset_ms: f64, rtt_ms: f64) {
+        self.samples.push_back(ClockSample { offset_ms, rtt_ms });
+        while self.samples.len() > HISTORY_LEN {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The sample with the lowest RTT in the window, which rejects outliers
+    /// caused by a congested or delayed exchange.
+    fn best_sample(&self) -> Option<ClockSample> {
+        self.samples
+            .iter()
+            .copied()
+            .min_by(|a, b| a.rtt_ms.partial_cmp(&b.rtt_ms).unwrap())
+    }
+}
+
+/// Per-neighbor clock offset/RTT estimates, keyed by endpoint id string.
+#[derive(Default)]
+pub struct ClockSyncTable {
+    neighbors: HashMap<String, NeighborClock>,
+}
+
+impl ClockSyncTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_sample(&mut self, neighbor: String, offset_ms: f64, rtt_ms: f64) {
+        self.neighbors.entry(neighbor).or_default().record(offset_ms, rtt_ms);
+    }
+
+    /// Best current offset estimate (offset ≈ remote_time − local_time), in
+    /// milliseconds, or `0.0` if we have no samples for this neighbor yet.
+    pub fn offset(&self, neighbor: &str) -> f64 {
+        self.neighbors
+            .get(neighbor)
+            .and_then(NeighborClock::best_sample)
+            .map(|s| s.offset_ms)
+            .unwrap_or(0.0)
+    }
+
+    /// Best current RTT estimate in milliseconds, or `None` if we have no
+    /// samples for this neighbor yet.
+    pub fn rtt(&self, neighbor: &str) -> Option<f64> {
+        self.neighbors
+            .get(neighbor)
+            .and_then(NeighborClock::best_sample)
+            .map(|s| s.rtt_ms)
+    }
+}
+
+/// Convert a remote `MediaChunk.timestamp` (the sender's wall clock, in ms)
+/// into a local playout deadline, by correcting for the estimated clock
+/// offset and adding the jitter buffer's target delay.
+///
+/// `offset_ms` is `remote_time − local_time` (see [`ClockSyncTable::offset`]),
+/// so the remote timestamp is corrected back to local time by subtracting it.
+pub fn playout_deadline(remote_timestamp_ms: u64, offset_ms: f64, jitter_delay_ms: u64) -> u64 {
+    let local_estimate = remote_timestamp_ms as f64 - offset_ms;
+    (local_estimate + jitter_delay_ms as f64).max(0.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_sample_symmetric_delay_and_positive_skew() {
+        // Remote clock is 1000ms ahead of local, with 20ms one-way delay
+        // each direction (40ms RTT): t1=0, t2=1020, t3=1025, t4=45.
+        let (offset, rtt) = compute_sample(0, 1020, 1025, 45);
+        assert_eq!(offset, 1000.0);
+        assert_eq!(rtt, 40.0);
+    }
+
+    #[test]
+    fn compute_sample_negative_skew() {
+        // Remote clock is 500ms behind local, symmetric 10ms one-way delay.
+        let (offset, rtt) = compute_sample(1000, 510, 515, 1025);
+        assert_eq!(offset, -500.0);
+        assert_eq!(rtt, 20.0);
+    }
+
+    #[test]
+    fn playout_deadline_corrects_remote_timestamp_to_local_time() {
+        // Remote is 1000ms ahead of local; a remote timestamp of 5000 should
+        // map to local time 4000, plus the jitter delay.
+        let deadline = playout_deadline(5000, 1000.0, 50);
+        assert_eq!(deadline, 4050);
+    }
+
+    #[test]
+    fn playout_deadline_handles_negative_skew() {
+        // Remote is 500ms behind local; a remote timestamp of 2000 should
+        // map to local time 2500, plus the jitter delay.
+        let deadline = playout_deadline(2000, -500.0, 20);
+        assert_eq!(deadline, 2520);
+    }
+}
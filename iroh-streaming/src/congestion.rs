@@ -0,0 +1,278 @@
+//! Receiver-driven congestion control for adaptive bitrate streaming.
+//!
+//! Viewers track how well they are receiving media from each source and
+//! periodically report loss/delay back to the mesh over the existing
+//! signaling channel. Whichever peer is broadcasting runs a simple AIMD
+//! loop over the aggregated feedback to pick a `StreamQuality` preset.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::node::StreamQuality;
+
+/// How far back the receive window looks when computing loss/delay stats.
+const WINDOW_MS: u64 = 2_000;
+
+/// One received chunk, recorded for loss/delay-gradient accounting.
+struct Sample {
+    sequence: u64,
+    send_timestamp: u64,
+    arrival_timestamp: u64,
+}
+
+/// Per-source sliding window of recently received chunks, used to derive
+/// loss fraction and delay gradient for congestion feedback.
+#[derive(Default)]
+pub struct ReceiveWindow {
+    samples: VecDeque<Sample>,
+    highest_seq: Option<u64>,
+}
+
+impl ReceiveWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a chunk with sender `send_timestamp` arriving at local wall-clock `now` (ms).
+    pub fn record(&mut self, sequence: u64, send_timestamp: u64, now: u64) {
+        self.highest_seq = Some(self.highest_seq.map_or(sequence, |h| h.max(sequence)));
+        self.samples.push_back(Sample {
+            sequence,
+            send_timestamp,
+            arrival_timestamp: now,
+        });
+        while let Some(front) = self.samples.front() {
+            if now.saturating_sub(front.arrival_timestamp) > WINDOW_MS {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Summarize the current window as a feedback sample, or `None` if nothing recorded yet.
+    pub fn feedback(&self) -> Option<FeedbackSample> {
+        let highest_seq = self.highest_seq?;
+        let first = self.samples.front()?;
+        let lowest_seq = first.sequence.min(highest_seq);
+        let expected = highest_seq.saturating_sub(lowest_seq) + 1;
+        let received = self.samples.len() as u64;
+        let loss_fraction = if expected > received {
+            (expected - received) as f32 / expected as f32
+        } else {
+            0.0
+        };
+
+        Some(FeedbackSample {
+            loss_fraction,
+            delay_trend: delay_gradient(&self.samples),
+            highest_seq,
+        })
+    }
+}
+
+/// Trend (ms per chunk) of inter-arrival time minus inter-send time,
+/// averaged across consecutive pairs in the window. Positive means the
+/// receive queue is building up relative to the sender's pacing.
+fn delay_gradient(samples: &VecDeque<Sample>) -> f32 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let pairs: Vec<_> = samples.iter().collect();
+    let mut total = 0i64;
+    let mut count = 0i64;
+    for window in pairs.windows(2) {
+        let (a, b) = (window[0], window[1]);
+        let inter_arrival = b.arrival_timestamp as i64 - a.arrival_timestamp as i64;
+        let inter_send = b.send_timestamp as i64 - a.send_timestamp as i64;
+        total += inter_arrival - inter_send;
+        count += 1;
+    }
+    total as f32 / count as f32
+}
+
+/// Compact feedback a viewer reports back over the signaling channel.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FeedbackSample {
+    pub loss_fraction: f32,
+    pub delay_trend: f32,
+    pub highest_seq: u64,
+}
+
+const LOSS_DECREASE_THRESHOLD: f32 = 0.10;
+const LOSS_INCREASE_THRESHOLD: f32 = 0.02;
+/// ms/chunk of sustained queue growth before we treat delay as "rising".
+const DELAY_RISING_THRESHOLD: f32 = 5.0;
+const MULTIPLICATIVE_DECREASE: f64 = 0.85;
+const ADDITIVE_INCREASE_KBPS: f64 = 100.0;
+
+fn kbps_for_quality(quality: StreamQuality) -> f64 {
+    match quality {
+        StreamQuality::Low => 300.0,
+        StreamQuality::Medium => 800.0,
+        StreamQuality::High => 2_000.0,
+        StreamQuality::Ultra => 4_000.0,
+    }
+}
+
+fn quality_for_kbps(kbps: f64) -> StreamQuality {
+    const TIERS: [StreamQuality; 4] = [
+        StreamQuality::Low,
+        StreamQuality::Medium,
+        StreamQuality::High,
+        StreamQuality::Ultra,
+    ];
+    TIERS
+        .into_iter()
+        .min_by(|a, b| {
+            let da = (kbps_for_quality(*a) - kbps).abs();
+            let db = (kbps_for_quality(*b) - kbps).abs();
+            da.partial_cmp(&db).unwrap()
+        })
+        .unwrap_or(StreamQuality::Medium)
+}
+
+/// AIMD bitrate controller: aggregates the latest feedback sample from each
+/// neighbor and adjusts a target bitrate up or down, mapped to the nearest
+/// `StreamQuality` preset.
+pub struct BitrateController {
+    target_kbps: f64,
+    quality: StreamQuality,
+    neighbor_feedback: HashMap<String, FeedbackSample>,
+}
+
+impl BitrateController {
+    pub fn new(initial: StreamQuality) -> Self {
+        Self {
+            target_kbps: kbps_for_quality(initial),
+            quality: initial,
+            neighbor_feedback: HashMap::new(),
+        }
+    }
+
+    pub fn quality(&self) -> StreamQuality {
+        self.quality
+    }
+
+    /// Record feedback from `from` and re-run the AIMD loop across all known
+    /// neighbors, returning the new quality if it changed.
+    pub fn record_feedback(&mut self, from: String, sample: FeedbackSample) -> Option<StreamQuality> {
+        self.neighbor_feedback.insert(from, sample);
+        self.rerun_aimd()
+    }
+
+    /// Drop a departed neighbor's feedback and re-run the AIMD loop, so a
+    /// stale congested (or healthy) sample left behind by a disconnected
+    /// neighbor can't permanently pin the target bitrate. Call this on
+    /// `StreamEvent::NeighborDown`.
+    pub fn remove_neighbor(&mut self, from: &str) -> Option<StreamQuality> {
+        if self.neighbor_feedback.remove(from).is_none() {
+            return None;
+        }
+        self.rerun_aimd()
+    }
+
+    /// Re-evaluate the AIMD loop against the current `neighbor_feedback`,
+    /// returning the new quality if it changed.
+    fn rerun_aimd(&mut self) -> Option<StreamQuality> {
+        let any_congested = self
+            .neighbor_feedback
+            .values()
+            .any(|s| s.loss_fraction > LOSS_DECREASE_THRESHOLD || s.delay_trend > DELAY_RISING_THRESHOLD);
+        let all_healthy = !self.neighbor_feedback.is_empty()
+            && self
+                .neighbor_feedback
+                .values()
+                .all(|s| s.loss_fraction < LOSS_INCREASE_THRESHOLD && s.delay_trend <= 0.0);
+
+        if any_congested {
+            self.target_kbps = (self.target_kbps * MULTIPLICATIVE_DECREASE).max(kbps_for_quality(StreamQuality::Low));
+        } else if all_healthy {
+            self.target_kbps = (self.target_kbps + ADDITIVE_INCREASE_KBPS).min(kbps_for_quality(StreamQuality::Ultra));
+        }
+
+        let new_quality = quality_for_kbps(self.target_kbps);
+        if new_quality != self.quality {
+            self.quality = new_quality;
+            Some(new_quality)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_feedback_increases_quality_once_healthy_enough() {
+        let mut controller = BitrateController::new(StreamQuality::Low);
+        let healthy = FeedbackSample {
+            loss_fraction: 0.0,
+            delay_trend: -1.0,
+            highest_seq: 0,
+        };
+        assert_eq!(controller.record_feedback("a".to_string(), healthy), None);
+        assert_eq!(controller.record_feedback("a".to_string(), healthy), None);
+        assert_eq!(
+            controller.record_feedback("a".to_string(), healthy),
+            Some(StreamQuality::Medium)
+        );
+        assert_eq!(controller.quality(), StreamQuality::Medium);
+    }
+
+    #[test]
+    fn record_feedback_decreases_quality_once_congested_enough() {
+        let mut controller = BitrateController::new(StreamQuality::Medium);
+        let congested = FeedbackSample {
+            loss_fraction: 0.5,
+            delay_trend: 0.0,
+            highest_seq: 0,
+        };
+        assert_eq!(controller.record_feedback("a".to_string(), congested), None);
+        assert_eq!(controller.record_feedback("a".to_string(), congested), None);
+        assert_eq!(
+            controller.record_feedback("a".to_string(), congested),
+            Some(StreamQuality::Low)
+        );
+        assert_eq!(controller.quality(), StreamQuality::Low);
+    }
+
+    #[test]
+    fn remove_neighbor_unsticks_a_permanently_congested_controller() {
+        let mut controller = BitrateController::new(StreamQuality::Medium);
+        let congested = FeedbackSample {
+            loss_fraction: 0.5,
+            delay_trend: 0.0,
+            highest_seq: 0,
+        };
+        // "a" reports heavy loss right before disconnecting; nothing else
+        // ever reports in, so without eviction `any_congested` would stay
+        // true forever.
+        controller.record_feedback("a".to_string(), congested);
+        assert_eq!(controller.remove_neighbor("a"), None);
+
+        let healthy = FeedbackSample {
+            loss_fraction: 0.0,
+            delay_trend: -1.0,
+            highest_seq: 0,
+        };
+        // With "a" gone, a fresh healthy neighbor should be able to push
+        // quality up again instead of being permanently vetoed.
+        for _ in 0..40 {
+            if controller.record_feedback("b".to_string(), healthy) == Some(StreamQuality::Ultra) {
+                return;
+            }
+        }
+        panic!("quality never recovered after the congested neighbor was removed");
+    }
+
+    #[test]
+    fn remove_neighbor_is_a_no_op_for_unknown_neighbor() {
+        let mut controller = BitrateController::new(StreamQuality::Medium);
+        assert_eq!(controller.remove_neighbor("ghost"), None);
+        assert_eq!(controller.quality(), StreamQuality::Medium);
+    }
+}
@@ -0,0 +1,217 @@
+//! Per-source jitter buffer for `MediaChunk` reordering and gap detection.
+//!
+//! Gossip delivers chunks with no ordering guarantees, so a slow or
+//! out-of-order arrival would otherwise be forwarded to JS exactly as it
+//! shows up. `JitterBuffer` holds recently-arrived chunks per source and
+//! only releases them once they are contiguous with the last emitted
+//! sequence, or once a configurable playout delay has elapsed and the gap
+//! is declared lost.
+
+use std::collections::BTreeMap;
+
+use crate::node::now_millis;
+
+/// Default playout delay, in milliseconds, a chunk may wait for earlier
+/// sequences to arrive before the buffer gives up on them.
+pub const DEFAULT_TARGET_DELAY_MS: u64 = 100;
+/// Default cap on how many out-of-order chunks are held per source.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// Tunables for `JitterBuffer`, settable at runtime via `Stream::set_jitter_buffer`.
+#[derive(Debug, Clone, Copy)]
+pub struct JitterBufferConfig {
+    pub target_delay_ms: u64,
+    pub max_depth: usize,
+}
+
+impl Default for JitterBufferConfig {
+    fn default() -> Self {
+        Self {
+            target_delay_ms: DEFAULT_TARGET_DELAY_MS,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+struct Buffered {
+    data: Vec<u8>,
+    timestamp: u64,
+    arrival: u64,
+}
+
+/// A chunk released by the buffer, in emit order.
+pub enum JitterEvent {
+    /// Chunk `sequence` is next in order and ready to hand to JS.
+    Ready {
+        sequence: u64,
+        data: Vec<u8>,
+        timestamp: u64,
+    },
+    /// `sequence` never arrived before its playout deadline.
+    Lost { sequence: u64 },
+}
+
+/// Per-source reordering buffer, keyed externally by the sending endpoint.
+#[derive(Default)]
+pub struct JitterBuffer {
+    pending: BTreeMap<u64, Buffered>,
+    last_emitted: Option<u64>,
+}
+
+impl JitterBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a freshly-received chunk, returning any chunks now ready to
+    /// emit (in order). Duplicates and chunks older than the last emitted
+    /// sequence are dropped silently.
+    pub fn push(
+        &mut self,
+        sequence: u64,
+        data: Vec<u8>,
+        timestamp: u64,
+        config: &JitterBufferConfig,
+    ) -> Vec<JitterEvent> {
+        if self.last_emitted.is_some_and(|last| sequence <= last) {
+            return Vec::new();
+        }
+        self.pending.insert(
+            sequence,
+            Buffered {
+                data,
+                timestamp,
+                arrival: now_millis(),
+            },
+        );
+        // Under depth pressure, drop the highest-sequence pending entries so
+        // memory stays bounded even if a large gap never resolves. These are
+        // the furthest from `next_seq`, so evicting them (rather than the
+        // lowest key) avoids discarding the very chunk that would close the
+        // gap and let emission resume.
+        while self.pending.len() > config.max_depth {
+            let Some(&newest) = self.pending.keys().next_back() else {
+                break;
+            };
+            self.pending.remove(&newest);
+        }
+        self.drain_ready(config)
+    }
+
+    /// Re-check pending gaps against the playout deadline without new input;
+    /// call this periodically so a stalled source still surfaces `ChunkLost`.
+    pub fn poll(&mut self, config: &JitterBufferConfig) -> Vec<JitterEvent> {
+        self.drain_ready(config)
+    }
+
+    fn drain_ready(&mut self, config: &JitterBufferConfig) -> Vec<JitterEvent> {
+        let mut events = Vec::new();
+        let now = now_millis();
+        loop {
+            let next_seq = self.last_emitted.map_or_else(
+                || self.pending.keys().next().copied().unwrap_or(0),
+                |s| s + 1,
+            );
+            match self.pending.first_key_value() {
+                Some((&seq, _)) if seq == next_seq => {
+                    let buffered = self.pending.remove(&seq).expect("just matched");
+                    self.last_emitted = Some(seq);
+                    events.push(JitterEvent::Ready {
+                        sequence: seq,
+                        data: buffered.data,
+                        timestamp: buffered.timestamp,
+                    });
+                }
+                Some((&seq, buffered)) if seq > next_seq => {
+                    if now.saturating_sub(buffered.arrival) < config.target_delay_ms {
+                        break;
+                    }
+                    for lost_seq in next_seq..seq {
+                        events.push(JitterEvent::Lost { sequence: lost_seq });
+                    }
+                    self.last_emitted = Some(seq - 1);
+                    // loop again: next_seq now equals seq, so the Ready arm fires.
+                }
+                _ => break,
+            }
+        }
+        events
+    }
+
+    /// Number of chunks currently buffered (not yet emitted or declared lost).
+    pub fn occupancy(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Sequence numbers currently known to be missing among what's buffered,
+    /// without waiting for the playout deadline. Used to drive early NACKs.
+    pub fn pending_gaps(&self) -> Vec<u64> {
+        let mut gaps = Vec::new();
+        let mut next_seq = self.last_emitted.map_or_else(
+            || self.pending.keys().next().copied().unwrap_or(0),
+            |s| s + 1,
+        );
+        for &seq in self.pending.keys() {
+            if seq > next_seq {
+                gaps.extend(next_seq..seq);
+            }
+            next_seq = seq + 1;
+        }
+        gaps
+    }
+
+    /// Treat `sequence` as resolved (permanently lost), advancing past it so
+    /// the usual deadline-based check doesn't also report it once it expires.
+    pub fn mark_lost(&mut self, sequence: u64) {
+        self.pending.remove(&sequence);
+        if self.last_emitted.map_or(true, |last| sequence > last) {
+            self.last_emitted = Some(sequence);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_evicts_highest_sequence_under_depth_pressure_not_lowest() {
+        let mut buf = JitterBuffer::default();
+        let config = JitterBufferConfig {
+            target_delay_ms: 10_000,
+            max_depth: 5,
+        };
+        // Seed pending = {16,17,18,19,20} after 9 was emitted, with no
+        // gap-filling chunk yet.
+        buf.last_emitted = Some(9);
+        for seq in 16..=20 {
+            buf.push(seq, vec![], 0, &config);
+        }
+        // Chunk 11 arrives and narrows the gap, but pushes len over
+        // max_depth: the highest sequence (20), not 11, must be evicted.
+        let events = buf.push(11, vec![], 0, &config);
+        assert!(events.is_empty(), "10 is still missing, so 11 must wait");
+        assert_eq!(buf.occupancy(), 5);
+        assert!(buf.pending.contains_key(&11), "gap-filling chunk must survive eviction");
+        assert!(!buf.pending.contains_key(&20), "highest sequence should be evicted first");
+    }
+
+    #[test]
+    fn push_declares_loss_after_deadline_elapses() {
+        let mut buf = JitterBuffer::default();
+        let config = JitterBufferConfig {
+            target_delay_ms: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        };
+        let ready = buf.push(5, b"hello".to_vec(), 100, &config);
+        assert!(matches!(ready.as_slice(), [JitterEvent::Ready { sequence: 5, .. }]));
+
+        // A gap opens: sequence 8 arrives while 6 and 7 never show up.
+        let events = buf.push(8, b"world".to_vec(), 400, &config);
+        assert!(events.iter().any(|e| matches!(e, JitterEvent::Lost { sequence: 6 })));
+        assert!(events.iter().any(|e| matches!(e, JitterEvent::Lost { sequence: 7 })));
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, JitterEvent::Ready { sequence: 8, .. })));
+    }
+}
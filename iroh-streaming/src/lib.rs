@@ -3,7 +3,12 @@
 //! This module provides real-time P2P streaming using gossip protocol.
 //! Based on browser-chat example from iroh-examples.
 
+pub mod clocksync;
+pub mod congestion;
+pub mod jitter;
 pub mod node;
+pub mod retransmit;
+pub mod stats;
 pub mod wasm;
 
 pub use node::*;
@@ -0,0 +1,332 @@
+//! Core streaming node built on iroh + iroh-gossip
+//!
+//! This module owns the iroh endpoint, the gossip topic plumbing, and the
+//! wire-level message types. `wasm.rs` is a thin wrapper around the types
+//! defined here. Based on browser-chat example from iroh-examples.
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use iroh::{Endpoint, EndpointId, SecretKey};
+use iroh_gossip::{
+    net::{Gossip, GOSSIP_ALPN},
+    proto::TopicId,
+};
+use n0_future::{boxed::BoxStream, StreamExt};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+
+/// Preset video/audio quality tiers a broadcaster can encode at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum StreamQuality {
+    Low,
+    Medium,
+    High,
+    Ultra,
+}
+
+impl StreamQuality {
+    /// `(width, height, fps)` to pass to `MediaRecorder`/`getUserMedia` constraints.
+    pub fn video_constraints(&self) -> (u32, u32, u32) {
+        match self {
+            StreamQuality::Low => (320, 240, 15),
+            StreamQuality::Medium => (640, 480, 24),
+            StreamQuality::High => (1280, 720, 30),
+            StreamQuality::Ultra => (1920, 1080, 30),
+        }
+    }
+
+    /// Audio bitrate in bits per second for this preset.
+    pub fn audio_bitrate(&self) -> u32 {
+        match self {
+            StreamQuality::Low => 24_000,
+            StreamQuality::Medium => 48_000,
+            StreamQuality::High => 64_000,
+            StreamQuality::Ultra => 96_000,
+        }
+    }
+
+    /// Lowercase name used on the wire and in the JS-facing API (`"low"`, `"medium"`, ...).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamQuality::Low => "low",
+            StreamQuality::Medium => "medium",
+            StreamQuality::High => "high",
+            StreamQuality::Ultra => "ultra",
+        }
+    }
+
+    /// Inverse of `as_str`; unrecognized names default to `Medium`.
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "low" => StreamQuality::Low,
+            "high" => StreamQuality::High,
+            "ultra" => StreamQuality::Ultra,
+            _ => StreamQuality::Medium,
+        }
+    }
+}
+
+/// All presets, lowest to highest, for nearest-available-layer lookups.
+pub const QUALITY_TIERS: [StreamQuality; 4] = [
+    StreamQuality::Low,
+    StreamQuality::Medium,
+    StreamQuality::High,
+    StreamQuality::Ultra,
+];
+
+/// A shareable handle to a stream topic: the topic id plus a set of
+/// bootstrap endpoints to dial when joining.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamTicket {
+    pub topic_id: TopicId,
+    pub bootstrap: BTreeSet<EndpointId>,
+}
+
+impl StreamTicket {
+    pub fn new(topic_id: TopicId) -> Self {
+        Self {
+            topic_id,
+            bootstrap: BTreeSet::new(),
+        }
+    }
+
+    pub fn new_random() -> Self {
+        Self::new(TopicId::from_bytes(rand::random()))
+    }
+
+    pub fn serialize_ticket(&self) -> String {
+        let bytes = postcard::to_stdvec(self).expect("StreamTicket is serializable");
+        data_encoding::BASE32_NOPAD.encode(&bytes).to_lowercase()
+    }
+
+    pub fn deserialize_ticket(s: &str) -> Result<Self> {
+        let bytes = data_encoding::BASE32_NOPAD
+            .decode(s.to_uppercase().as_bytes())
+            .context("invalid ticket encoding")?;
+        postcard::from_bytes(&bytes).context("invalid ticket contents")
+    }
+}
+
+/// Wire-level messages gossiped around a stream topic.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Message {
+    Presence { name: String, sent_timestamp: u64 },
+    MediaChunk {
+        sequence: u64,
+        timestamp: u64,
+        layer: StreamQuality,
+        data: Vec<u8>,
+    },
+    Signal { data: Vec<u8>, timestamp: u64 },
+}
+
+/// Events surfaced to callers (and, through `wasm.rs`, to JavaScript).
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    NeighborUp { endpoint_id: EndpointId },
+    NeighborDown { endpoint_id: EndpointId },
+    Presence {
+        from: EndpointId,
+        name: String,
+        sent_timestamp: u64,
+    },
+    MediaChunk {
+        from: EndpointId,
+        data: Vec<u8>,
+        sequence: u64,
+        timestamp: u64,
+        layer: StreamQuality,
+    },
+    Signal {
+        from: EndpointId,
+        data: Vec<u8>,
+        timestamp: u64,
+    },
+    Lagged,
+}
+
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Sending half of a joined stream: broadcasts chunks, presence and
+/// signaling payloads to the topic.
+#[derive(Clone)]
+pub struct StreamSender {
+    sender: iroh_gossip::api::GossipSender,
+    name: Arc<Mutex<String>>,
+}
+
+impl StreamSender {
+    fn new(sender: iroh_gossip::api::GossipSender) -> Self {
+        Self {
+            sender,
+            name: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    async fn broadcast(&self, msg: &Message) -> Result<()> {
+        let bytes = postcard::to_stdvec(msg)?;
+        self.sender.broadcast(bytes.into()).await?;
+        Ok(())
+    }
+
+    /// Like `broadcast`, but sent only to directly connected neighbors
+    /// instead of flooded across the whole topic.
+    async fn broadcast_direct(&self, msg: &Message) -> Result<()> {
+        let bytes = postcard::to_stdvec(msg)?;
+        self.sender.broadcast_neighbors(bytes.into()).await?;
+        Ok(())
+    }
+
+    /// Broadcast one media chunk carrying sequence number `sequence` on
+    /// simulcast layer `layer`. Sequence numbers are independent per layer.
+    pub async fn broadcast_layer_chunk(
+        &self,
+        data: Vec<u8>,
+        sequence: u64,
+        layer: StreamQuality,
+    ) -> Result<()> {
+        self.broadcast(&Message::MediaChunk {
+            sequence,
+            timestamp: now_millis(),
+            layer,
+            data,
+        })
+        .await
+    }
+
+    /// Announce this node's display name to the topic.
+    pub async fn send_presence(&self) -> Result<()> {
+        let name = self.name.lock().unwrap().clone();
+        self.broadcast(&Message::Presence {
+            name,
+            sent_timestamp: now_millis(),
+        })
+        .await
+    }
+
+    /// Send an out-of-band signaling payload (e.g. SDP/ICE data).
+    pub async fn send_signal(&self, data: Vec<u8>) -> Result<()> {
+        self.broadcast(&Message::Signal {
+            data,
+            timestamp: now_millis(),
+        })
+        .await
+    }
+
+    /// Like `send_signal`, but sent only to directly connected neighbors —
+    /// used for low-amplification replies such as retransmitted chunks.
+    pub async fn send_signal_direct(&self, data: Vec<u8>) -> Result<()> {
+        self.broadcast_direct(&Message::Signal {
+            data,
+            timestamp: now_millis(),
+        })
+        .await
+    }
+
+    /// Set the display name advertised by future `send_presence` calls.
+    pub fn set_name(&self, name: String) {
+        *self.name.lock().unwrap() = name;
+    }
+}
+
+/// The local iroh endpoint plus the gossip protocol handler.
+pub struct StreamingNode {
+    endpoint: Endpoint,
+    gossip: Gossip,
+}
+
+impl StreamingNode {
+    /// Spawn a new node, optionally with a fixed `SecretKey` for a stable identity.
+    pub async fn spawn(secret_key: Option<SecretKey>) -> Result<Self> {
+        let secret_key = secret_key.unwrap_or_else(|| SecretKey::generate(OsRng));
+        let endpoint = Endpoint::builder()
+            .secret_key(secret_key)
+            .alpns(vec![GOSSIP_ALPN.to_vec()])
+            .bind()
+            .await?;
+        let gossip = Gossip::builder().spawn(endpoint.clone());
+
+        Ok(Self { endpoint, gossip })
+    }
+
+    pub fn endpoint_id(&self) -> EndpointId {
+        self.endpoint.id()
+    }
+
+    /// Join (or create) the topic named by `ticket`, announcing ourselves as `name`.
+    pub async fn join(
+        &self,
+        ticket: &StreamTicket,
+        name: String,
+    ) -> Result<(StreamSender, BoxStream<Result<StreamEvent>>)> {
+        let bootstrap: Vec<EndpointId> = ticket.bootstrap.iter().cloned().collect();
+        let topic = self
+            .gossip
+            .subscribe(ticket.topic_id, bootstrap)
+            .await?;
+        let (sender, receiver) = topic.split();
+
+        let stream_sender = StreamSender::new(sender);
+        stream_sender.set_name(name);
+        stream_sender.send_presence().await.ok();
+
+        let events = Box::pin(receiver.filter_map(|event| async move {
+            match event {
+                Ok(iroh_gossip::api::Event::NeighborUp(endpoint_id)) => {
+                    Some(Ok(StreamEvent::NeighborUp { endpoint_id }))
+                }
+                Ok(iroh_gossip::api::Event::NeighborDown(endpoint_id)) => {
+                    Some(Ok(StreamEvent::NeighborDown { endpoint_id }))
+                }
+                Ok(iroh_gossip::api::Event::Received(msg)) => {
+                    match postcard::from_bytes::<Message>(&msg.content) {
+                        Ok(Message::Presence { name, sent_timestamp }) => {
+                            Some(Ok(StreamEvent::Presence {
+                                from: msg.delivered_from,
+                                name,
+                                sent_timestamp,
+                            }))
+                        }
+                        Ok(Message::MediaChunk { sequence, timestamp, layer, data }) => {
+                            Some(Ok(StreamEvent::MediaChunk {
+                                from: msg.delivered_from,
+                                data,
+                                sequence,
+                                timestamp,
+                                layer,
+                            }))
+                        }
+                        Ok(Message::Signal { data, timestamp }) => {
+                            Some(Ok(StreamEvent::Signal {
+                                from: msg.delivered_from,
+                                data,
+                                timestamp,
+                            }))
+                        }
+                        Err(e) => {
+                            tracing::warn!("failed to decode gossip message: {e}");
+                            None
+                        }
+                    }
+                }
+                Ok(iroh_gossip::api::Event::Lagged) => Some(Ok(StreamEvent::Lagged)),
+                Err(e) => Some(Err(e.into())),
+            }
+        })) as BoxStream<Result<StreamEvent>>;
+
+        Ok((stream_sender, events))
+    }
+
+    /// Tear down the endpoint and all active gossip subscriptions.
+    pub async fn shutdown(&self) {
+        self.endpoint.close().await;
+    }
+}
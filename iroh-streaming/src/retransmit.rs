@@ -0,0 +1,117 @@
+//! Optional NACK-based selective retransmission of lost `MediaChunk`s.
+//!
+//! Gossip delivery gives no reliability guarantee, so a dropped chunk is
+//! simply gone. When enabled (`StreamSender::enable_retransmission`), a
+//! viewer that notices a gap in a source's sequence asks for it back over
+//! the existing `send_signal` channel; the original broadcaster keeps a
+//! short ring buffer of recently sent chunks and replays whichever ones it
+//! still has, or says so when it doesn't.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::node::{now_millis, StreamQuality};
+
+/// How long a sent chunk stays eligible for retransmission.
+const RING_BUFFER_WINDOW_MS: u64 = 2_000;
+/// Rolling window over which the per-window retransmit cap is enforced.
+const RATE_WINDOW_MS: u64 = 1_000;
+/// Most chunks a broadcaster will replay within one `RATE_WINDOW_MS` window,
+/// regardless of how many are requested, so a lossy link can't trigger a storm.
+const MAX_RETRANSMITS_PER_WINDOW: usize = 20;
+
+/// Messages exchanged over `send_signal` to request and serve retransmits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RetransmitMessage {
+    /// Sent by a viewer that detected a gap in `layer`'s sequence from `from`.
+    Nack {
+        from: String,
+        requester: String,
+        layer: StreamQuality,
+        missing_sequences: Vec<u64>,
+    },
+    /// A replayed chunk, sent by the original broadcaster in answer to a `Nack`.
+    Resend {
+        requester: String,
+        layer: StreamQuality,
+        sequence: u64,
+        timestamp: u64,
+        data: Vec<u8>,
+    },
+    /// Sent instead of `Resend` when `sequence` has already aged out of the ring buffer.
+    TooOld {
+        requester: String,
+        layer: StreamQuality,
+        sequence: u64,
+    },
+}
+
+struct SentChunk {
+    layer: StreamQuality,
+    sequence: u64,
+    timestamp: u64,
+    sent_at: u64,
+    data: Vec<u8>,
+}
+
+/// Serving side of retransmission: a ring buffer of recently sent chunks
+/// plus a rate limiter, so a broadcaster can answer `Nack`s without being
+/// driven into a retransmit storm by a lossy link.
+#[derive(Default)]
+pub struct RetransmitServer {
+    entries: VecDeque<SentChunk>,
+    window_start: u64,
+    window_count: usize,
+}
+
+impl RetransmitServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a chunk just handed to `broadcast_layer_chunk`.
+    pub fn record(&mut self, layer: StreamQuality, sequence: u64, timestamp: u64, data: Vec<u8>) {
+        let now = now_millis();
+        self.entries.push_back(SentChunk {
+            layer,
+            sequence,
+            timestamp,
+            sent_at: now,
+            data,
+        });
+        while let Some(front) = self.entries.front() {
+            if now.saturating_sub(front.sent_at) > RING_BUFFER_WINDOW_MS {
+                self.entries.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Look up `sequence` to answer a `Nack`, consuming one slot of this
+    /// window's retransmit budget. `None` means the budget is already spent
+    /// and the request should be dropped outright (no reply at all).
+    /// `Some(None)` means answer with `TooOld`; `Some(Some(..))` with `Resend`.
+    pub fn try_fetch(&mut self, layer: StreamQuality, sequence: u64) -> Option<Option<(u64, Vec<u8>)>> {
+        let now = now_millis();
+        if now.saturating_sub(self.window_start) >= RATE_WINDOW_MS {
+            self.window_start = now;
+            self.window_count = 0;
+        }
+        if self.window_count >= MAX_RETRANSMITS_PER_WINDOW {
+            return None;
+        }
+        self.window_count += 1;
+        Some(
+            self.entries
+                .iter()
+                .find(|c| {
+                    c.layer == layer
+                        && c.sequence == sequence
+                        && now.saturating_sub(c.sent_at) <= RING_BUFFER_WINDOW_MS
+                })
+                .map(|c| (c.timestamp, c.data.clone())),
+        )
+    }
+}
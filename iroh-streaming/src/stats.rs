@@ -0,0 +1,174 @@
+//! Live streaming statistics, analogous to a WebRTC stats report.
+//!
+//! Counters are threaded through the `Stream` receive loop and
+//! `StreamSender` broadcast methods, then rolled up into a JSON snapshot on
+//! demand (`Stream::stats`) or on a fixed interval (`Stream::stats_stream`).
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::clocksync::ClockSyncTable;
+use crate::node::now_millis;
+
+/// How often a rolling byte counter recomputes its bitrate (ms).
+const BITRATE_WINDOW_MS: u64 = 1_000;
+
+/// Tracks bytes moved over a rolling window to derive a bits-per-second rate.
+struct ByteCounter {
+    window_start: u64,
+    window_bytes: u64,
+    bitrate_bps: f64,
+}
+
+impl Default for ByteCounter {
+    fn default() -> Self {
+        Self {
+            window_start: now_millis(),
+            window_bytes: 0,
+            bitrate_bps: 0.0,
+        }
+    }
+}
+
+impl ByteCounter {
+    fn add(&mut self, bytes: u64) {
+        let now = now_millis();
+        let elapsed = now.saturating_sub(self.window_start);
+        if elapsed >= BITRATE_WINDOW_MS {
+            self.bitrate_bps = self.window_bytes as f64 * 8.0 / (elapsed.max(1) as f64 / 1000.0);
+            self.window_start = now;
+            self.window_bytes = 0;
+        }
+        self.window_bytes += bytes;
+    }
+}
+
+/// RFC 3550 §6.4.1 style running estimate of inter-arrival jitter: the mean
+/// deviation of the difference in packet spacing between sender and receiver.
+#[derive(Default)]
+struct JitterEstimator {
+    prev_transit_ms: Option<i64>,
+    estimate_ms: f64,
+}
+
+impl JitterEstimator {
+    fn update(&mut self, send_timestamp_ms: u64, arrival_ms: u64) {
+        let transit = arrival_ms as i64 - send_timestamp_ms as i64;
+        if let Some(prev_transit) = self.prev_transit_ms {
+            let d = (transit - prev_transit).unsigned_abs() as f64;
+            self.estimate_ms += (d - self.estimate_ms) / 16.0;
+        }
+        self.prev_transit_ms = Some(transit);
+    }
+}
+
+/// Cumulative receive-side bookkeeping for one neighbor.
+#[derive(Default)]
+struct NeighborReceiveStats {
+    bytes: ByteCounter,
+    jitter: JitterEstimator,
+    received: u64,
+    lowest_seq: Option<u64>,
+    highest_seq: Option<u64>,
+}
+
+impl NeighborReceiveStats {
+    fn record_chunk(&mut self, bytes: usize, sequence: u64, timestamp: u64) {
+        self.bytes.add(bytes as u64);
+        self.jitter.update(timestamp, now_millis());
+        self.received += 1;
+        self.lowest_seq = Some(self.lowest_seq.map_or(sequence, |s| s.min(sequence)));
+        self.highest_seq = Some(self.highest_seq.map_or(sequence, |s| s.max(sequence)));
+    }
+
+    fn loss_fraction(&self) -> f32 {
+        let (Some(lowest), Some(highest)) = (self.lowest_seq, self.highest_seq) else {
+            return 0.0;
+        };
+        let expected = highest.saturating_sub(lowest) + 1;
+        if expected <= self.received {
+            0.0
+        } else {
+            (expected - self.received) as f32 / expected as f32
+        }
+    }
+}
+
+/// Per-neighbor and aggregate statistics, accumulated over the life of a `Stream`.
+#[derive(Default)]
+pub struct StatsCollector {
+    send_bytes: ByteCounter,
+    receive: HashMap<String, NeighborReceiveStats>,
+    neighbor_churn: u64,
+}
+
+impl StatsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_sent_chunk(&mut self, bytes: usize) {
+        self.send_bytes.add(bytes as u64);
+    }
+
+    /// `key` is `"{endpoint_id}#{layer}"` (the same keys as the
+    /// `jitter_buffers` map in `wasm::Stream`), so
+    /// switching simulcast layers mid-stream starts fresh loss/jitter
+    /// accounting instead of mixing two independent sequence spaces.
+    pub fn record_received_chunk(&mut self, key: &str, bytes: usize, sequence: u64, timestamp: u64) {
+        self.receive
+            .entry(key.to_string())
+            .or_default()
+            .record_chunk(bytes, sequence, timestamp);
+    }
+
+    /// Call on every `NeighborUp`/`NeighborDown` event to track churn.
+    pub fn record_neighbor_churn(&mut self) {
+        self.neighbor_churn += 1;
+    }
+
+    pub fn snapshot(&self, neighbor_count: usize, clock_sync: &ClockSyncTable) -> StatsSnapshot {
+        let neighbors = self
+            .receive
+            .iter()
+            .map(|(key, s)| {
+                let endpoint_id = key.rsplit_once('#').map_or(key.as_str(), |(from, _layer)| from);
+                (
+                    key.clone(),
+                    NeighborStatsSnapshot {
+                        receive_bitrate_bps: s.bytes.bitrate_bps,
+                        loss_fraction: s.loss_fraction(),
+                        jitter_ms: s.jitter.estimate_ms,
+                        rtt_ms: clock_sync.rtt(endpoint_id),
+                    },
+                )
+            })
+            .collect();
+
+        StatsSnapshot {
+            send_bitrate_bps: self.send_bytes.bitrate_bps,
+            neighbor_count,
+            neighbor_churn: self.neighbor_churn,
+            neighbors,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeighborStatsSnapshot {
+    pub receive_bitrate_bps: f64,
+    pub loss_fraction: f32,
+    pub jitter_ms: f64,
+    pub rtt_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsSnapshot {
+    pub send_bitrate_bps: f64,
+    pub neighbor_count: usize,
+    pub neighbor_churn: u64,
+    pub neighbors: HashMap<String, NeighborStatsSnapshot>,
+}
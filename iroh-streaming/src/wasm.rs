@@ -3,8 +3,12 @@
 //! This module exposes the streaming node functionality to JavaScript
 //! using wasm-bindgen. Based on browser-chat example.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
 
 use anyhow::Result;
 use js_sys::Uint8Array;
@@ -16,7 +20,12 @@ use wasm_bindgen::{JsError, JsValue, prelude::wasm_bindgen};
 use wasm_streams::ReadableStream;
 use web_sys::console;
 
-use crate::node::{self, StreamTicket, StreamEvent, StreamQuality};
+use crate::clocksync::{ClockSyncMessage, ClockSyncTable};
+use crate::congestion::{BitrateController, FeedbackSample, ReceiveWindow};
+use crate::jitter::{JitterBuffer, JitterBufferConfig, JitterEvent};
+use crate::node::{self, StreamTicket, StreamEvent, StreamQuality, QUALITY_TIERS};
+use crate::retransmit::{RetransmitMessage, RetransmitServer};
+use crate::stats::{StatsCollector, StatsSnapshot};
 
 #[wasm_bindgen(start)]
 fn start() {
@@ -82,13 +91,7 @@ impl StreamingNode {
 
     /// Get video constraints for a quality preset
     pub fn get_quality_constraints(quality: String) -> String {
-        let q = match quality.as_str() {
-            "low" => StreamQuality::Low,
-            "medium" => StreamQuality::Medium,
-            "high" => StreamQuality::High,
-            "ultra" => StreamQuality::Ultra,
-            _ => StreamQuality::Medium,
-        };
+        let q = StreamQuality::parse(&quality);
 
         let (width, height, fps) = q.video_constraints();
         let audio_bitrate = q.audio_bitrate();
@@ -114,6 +117,16 @@ pub struct Stream {
     sender: StreamSender,
     receiver: StreamReceiver,
     ticket: StreamTicket,
+    jitter_buffers: Arc<Mutex<HashMap<String, JitterBuffer>>>,
+    jitter_config: Arc<Mutex<JitterBufferConfig>>,
+    clock_sync: Arc<Mutex<ClockSyncTable>>,
+    stats: Arc<Mutex<StatsCollector>>,
+    stats_stream: StreamReceiver,
+    selected_layer: Arc<Mutex<StreamQuality>>,
+    available_layers: Arc<Mutex<BTreeSet<StreamQuality>>>,
+    /// Flipped by `Drop` so the background loops below stop polling once JS
+    /// drops this `Stream`; otherwise they'd outlive it and leak forever.
+    shutdown: Arc<AtomicBool>,
 }
 
 impl Stream {
@@ -128,25 +141,125 @@ impl Stream {
         let neighbors = Arc::new(Mutex::new(BTreeSet::new()));
         let neighbors2 = neighbors.clone();
 
+        // Flipped by `Drop` to stop the background loops spawned below once
+        // this `Stream` is no longer reachable from JS.
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        // Receiver-driven congestion control: toggled via `StreamSender::enable_congestion_control`.
+        let congestion_enabled = Arc::new(AtomicBool::new(false));
+        let congestion_windows: Arc<Mutex<HashMap<String, ReceiveWindow>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+        let bitrate_controller = Arc::new(Mutex::new(BitrateController::new(StreamQuality::Medium)));
+
+        spawn_feedback_loop(
+            sender.clone(),
+            congestion_enabled.clone(),
+            congestion_windows.clone(),
+            shutdown.clone(),
+        );
+
+        // Per-source jitter buffer, reordering/gap-detecting ahead of JS delivery.
+        let jitter_buffers: Arc<Mutex<HashMap<String, JitterBuffer>>> = Arc::new(Mutex::new(HashMap::new()));
+        let jitter_config = Arc::new(Mutex::new(JitterBufferConfig::default()));
+        // Chunks/losses the jitter buffer released ahead of the event that
+        // triggered them; drained before pulling the next gossip event.
+        let pending_out: Arc<Mutex<VecDeque<JsValue>>> = Arc::new(Mutex::new(VecDeque::new()));
+        // Wakes the consumer below when the tick loop pushes into
+        // `pending_out` while the consumer is parked on `receiver.next()`.
+        let wake = WakeSignal::default();
+
+        spawn_jitter_tick_loop(
+            jitter_buffers.clone(),
+            jitter_config.clone(),
+            pending_out.clone(),
+            wake.clone(),
+            shutdown.clone(),
+        );
+
+        // NTP-style clock sync: always on, not behind a feature toggle.
+        let clock_sync: Arc<Mutex<ClockSyncTable>> = Arc::new(Mutex::new(ClockSyncTable::new()));
+        let me_id = me.to_string();
+        spawn_clocksync_probe_loop(sender.clone(), me_id.clone(), shutdown.clone());
+
+        // Live statistics, always collected; surfaced via `stats()`/`stats_stream()`.
+        let stats: Arc<Mutex<StatsCollector>> = Arc::new(Mutex::new(StatsCollector::new()));
+
+        // Simulcast: which layers a broadcaster has actually been observed
+        // sending, and which one this viewer currently wants.
+        let selected_layer = Arc::new(Mutex::new(StreamQuality::Medium));
+        let available_layers: Arc<Mutex<BTreeSet<StreamQuality>>> = Arc::new(Mutex::new(BTreeSet::new()));
+
+        // NACK-based retransmission: toggled via `StreamSender::enable_retransmission`.
+        let retransmit_enabled = Arc::new(AtomicBool::new(false));
+        let retransmit_server: Arc<Mutex<RetransmitServer>> = Arc::new(Mutex::new(RetransmitServer::new()));
+        spawn_nack_loop(
+            sender.clone(),
+            retransmit_enabled.clone(),
+            jitter_buffers.clone(),
+            me_id.clone(),
+            shutdown.clone(),
+        );
+
         // Convert receiver to JS ReadableStream using try_unfold pattern
         let receiver_stream = n0_future::stream::try_unfold(receiver, {
             let neighbors = neighbors2.clone();
+            let congestion_enabled = congestion_enabled.clone();
+            let congestion_windows = congestion_windows.clone();
+            let bitrate_controller = bitrate_controller.clone();
+            let jitter_buffers = jitter_buffers.clone();
+            let jitter_config = jitter_config.clone();
+            let pending_out = pending_out.clone();
+            let wake = wake.clone();
+            let clock_sync = clock_sync.clone();
+            let clocksync_sender = sender.clone();
+            let me_id = me_id.clone();
+            let stats = stats.clone();
+            let selected_layer = selected_layer.clone();
+            let available_layers = available_layers.clone();
+            let retransmit_enabled = retransmit_enabled.clone();
+            let retransmit_server = retransmit_server.clone();
+            let retransmit_sender = sender.clone();
             move |mut receiver| {
                 let neighbors = neighbors.clone();
+                let congestion_enabled = congestion_enabled.clone();
+                let congestion_windows = congestion_windows.clone();
+                let bitrate_controller = bitrate_controller.clone();
+                let jitter_buffers = jitter_buffers.clone();
+                let jitter_config = jitter_config.clone();
+                let pending_out = pending_out.clone();
+                let wake = wake.clone();
+                let clock_sync = clock_sync.clone();
+                let clocksync_sender = clocksync_sender.clone();
+                let me_id = me_id.clone();
+                let stats = stats.clone();
+                let selected_layer = selected_layer.clone();
+                let available_layers = available_layers.clone();
+                let retransmit_enabled = retransmit_enabled.clone();
+                let retransmit_server = retransmit_server.clone();
+                let retransmit_sender = retransmit_sender.clone();
                 async move {
                     loop {
-                        let Some(event) = receiver.next().await else {
-                            tracing::info!("[WASM] Receiver stream ended");
-                            return Ok(None);
+                        if let Some(value) = pending_out.lock().unwrap().pop_front() {
+                            return Ok(Some((value, receiver)));
+                        }
+
+                        let event = match next_or_woken(&mut receiver, &wake).await {
+                            NextOrWoken::Woken => continue,
+                            NextOrWoken::Event(None) => {
+                                tracing::info!("[WASM] Receiver stream ended");
+                                return Ok(None);
+                            }
+                            NextOrWoken::Event(Some(event)) => event,
                         };
-                        
+
                         tracing::debug!("[WASM] Received event from stream");
-                        
+
                         match event {
                             Ok(StreamEvent::NeighborUp { endpoint_id }) => {
                                 let id = endpoint_id.to_string();
                                 tracing::info!("[WASM] NeighborUp: {}", id);
                                 neighbors.lock().unwrap().insert(id.clone());
+                                stats.lock().unwrap().record_neighbor_churn();
                                 let js_event = WasmStreamEvent::NeighborUp { endpoint_id: id };
                                 let value = serde_wasm_bindgen::to_value(&js_event).unwrap();
                                 return Ok(Some((value, receiver)));
@@ -155,6 +268,20 @@ impl Stream {
                                 let id = endpoint_id.to_string();
                                 tracing::info!("[WASM] NeighborDown: {}", id);
                                 neighbors.lock().unwrap().remove(&id);
+                                stats.lock().unwrap().record_neighbor_churn();
+                                // Drop the departed neighbor's congestion feedback so a
+                                // stale congested sample can't permanently pin the AIMD
+                                // loop at a low bitrate.
+                                if let Some(quality) = bitrate_controller.lock().unwrap().remove_neighbor(&id) {
+                                    tracing::info!("[WASM] Congestion control switched quality to {} after neighbor departed", quality.as_str());
+                                    let js_event = WasmStreamEvent::QualityChange {
+                                        quality: quality.as_str().to_string(),
+                                    };
+                                    pending_out
+                                        .lock()
+                                        .unwrap()
+                                        .push_back(serde_wasm_bindgen::to_value(&js_event).unwrap());
+                                }
                                 let js_event = WasmStreamEvent::NeighborDown { endpoint_id: id };
                                 let value = serde_wasm_bindgen::to_value(&js_event).unwrap();
                                 return Ok(Some((value, receiver)));
@@ -169,18 +296,178 @@ impl Stream {
                                 let value = serde_wasm_bindgen::to_value(&js_event).unwrap();
                                 return Ok(Some((value, receiver)));
                             }
-                            Ok(StreamEvent::MediaChunk { from, data, sequence, timestamp }) => {
-                                tracing::info!("[WASM] MediaChunk from {} seq={} size={}", from, sequence, data.len());
-                                let js_event = WasmStreamEvent::MediaChunk {
-                                    from: from.to_string(),
-                                    data,
-                                    sequence,
-                                    timestamp,
+                            Ok(StreamEvent::MediaChunk { from, data, sequence, timestamp, layer }) => {
+                                let target = {
+                                    let mut available = available_layers.lock().unwrap();
+                                    available.insert(layer);
+                                    let wanted = *selected_layer.lock().unwrap();
+                                    nearest_available_layer(wanted, &available)
                                 };
-                                let value = serde_wasm_bindgen::to_value(&js_event).unwrap();
+                                if target != Some(layer) {
+                                    // Not the layer this viewer currently wants; a
+                                    // better-matching layer will still arrive.
+                                    continue;
+                                }
+                                tracing::info!("[WASM] MediaChunk from {} seq={} size={} layer={}", from, sequence, data.len(), layer.as_str());
+                                let from = from.to_string();
+                                // Keyed by (source, layer): switching layers starts a
+                                // fresh buffer/window/stat rather than mixing two
+                                // independent sequence spaces.
+                                let buffer_key = format!("{from}#{}", layer.as_str());
+                                stats
+                                    .lock()
+                                    .unwrap()
+                                    .record_received_chunk(&buffer_key, data.len(), sequence, timestamp);
+                                if congestion_enabled.load(Ordering::Relaxed) {
+                                    let mut windows = congestion_windows.lock().unwrap();
+                                    windows
+                                        .entry(buffer_key.clone())
+                                        .or_default()
+                                        .record(sequence, timestamp, node::now_millis());
+                                }
+                                let released = {
+                                    let config = *jitter_config.lock().unwrap();
+                                    jitter_buffers
+                                        .lock()
+                                        .unwrap()
+                                        .entry(buffer_key)
+                                        .or_default()
+                                        .push(sequence, data, timestamp, &config)
+                                };
+                                let mut out = jitter_events_to_js(&from, layer, released);
+                                let Some(value) = out.pop_front() else {
+                                    // Still waiting on earlier sequences or the playout deadline.
+                                    continue;
+                                };
+                                pending_out.lock().unwrap().extend(out);
                                 return Ok(Some((value, receiver)));
                             }
                             Ok(StreamEvent::Signal { from, data, timestamp }) => {
+                                if let Some(msg) = decode_clocksync(&data) {
+                                    match msg {
+                                        ClockSyncMessage::Probe { requester, t1 } => {
+                                            let t2 = node::now_millis();
+                                            let response = ClockSyncMessage::Response {
+                                                requester,
+                                                t1,
+                                                t2,
+                                                t3: node::now_millis(),
+                                            };
+                                            if let Err(e) = clocksync_sender.send_signal(encode_clocksync(&response)).await {
+                                                tracing::warn!("[WASM] Failed to send clock-sync response: {:?}", e);
+                                            }
+                                        }
+                                        ClockSyncMessage::Response { requester, t1, t2, t3 } => {
+                                            if requester == me_id {
+                                                let t4 = node::now_millis();
+                                                let (offset, rtt) = crate::clocksync::compute_sample(t1, t2, t3, t4);
+                                                clock_sync.lock().unwrap().record_sample(from.to_string(), offset, rtt);
+                                            }
+                                        }
+                                    }
+                                    continue;
+                                }
+                                // The magic-byte tag identifies these as internal
+                                // protocol envelopes regardless of local toggle
+                                // state, so always swallow them here even when
+                                // the corresponding feature is disabled locally
+                                // — otherwise a peer with mismatched settings
+                                // would leak raw envelope bytes to JS as if they
+                                // were app-level signaling data.
+                                if let Some((source, sample)) = decode_feedback(&data) {
+                                    if congestion_enabled.load(Ordering::Relaxed) && source == me_id {
+                                        tracing::debug!(
+                                            "[WASM] Congestion feedback from {} about {}: {:?}",
+                                            from, source, sample
+                                        );
+                                        let changed = bitrate_controller
+                                            .lock()
+                                            .unwrap()
+                                            .record_feedback(from.to_string(), sample);
+                                        if let Some(quality) = changed {
+                                            tracing::info!("[WASM] Congestion control switched quality to {}", quality.as_str());
+                                            let js_event = WasmStreamEvent::QualityChange {
+                                                quality: quality.as_str().to_string(),
+                                            };
+                                            let value = serde_wasm_bindgen::to_value(&js_event).unwrap();
+                                            return Ok(Some((value, receiver)));
+                                        }
+                                    }
+                                    continue;
+                                }
+                                // Same rationale as the congestion-feedback decode
+                                // above: the tag is unambiguous regardless of the
+                                // local toggle, so always decode and only gate the
+                                // reaction (actually serving a resend).
+                                if let Some(msg) = decode_retransmit(&data) {
+                                    if retransmit_enabled.load(Ordering::Relaxed) {
+                                        match msg {
+                                            RetransmitMessage::Nack { from: nacked, requester, layer, missing_sequences } => {
+                                                if nacked == me_id {
+                                                    for sequence in missing_sequences {
+                                                        let fetched = retransmit_server.lock().unwrap().try_fetch(layer, sequence);
+                                                        let Some(found) = fetched else {
+                                                            // Rate budget for this window is spent; drop silently.
+                                                            break;
+                                                        };
+                                                        let reply = match found {
+                                                            Some((timestamp, data)) => RetransmitMessage::Resend {
+                                                                requester: requester.clone(),
+                                                                layer,
+                                                                sequence,
+                                                                timestamp,
+                                                                data,
+                                                            },
+                                                            None => RetransmitMessage::TooOld {
+                                                                requester: requester.clone(),
+                                                                layer,
+                                                                sequence,
+                                                            },
+                                                        };
+                                                        if let Err(e) = retransmit_sender.send_signal_direct(encode_retransmit(&reply)).await {
+                                                            tracing::warn!("[WASM] Failed to send retransmit reply: {:?}", e);
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                            RetransmitMessage::Resend { requester, layer, sequence, timestamp, data } => {
+                                                if requester == me_id {
+                                                    let from = from.to_string();
+                                                    let buffer_key = format!("{from}#{}", layer.as_str());
+                                                    let released = {
+                                                        let config = *jitter_config.lock().unwrap();
+                                                        jitter_buffers
+                                                            .lock()
+                                                            .unwrap()
+                                                            .entry(buffer_key)
+                                                            .or_default()
+                                                            .push(sequence, data, timestamp, &config)
+                                                    };
+                                                    let mut out = jitter_events_to_js(&from, layer, released);
+                                                    if let Some(value) = out.pop_front() {
+                                                        pending_out.lock().unwrap().extend(out);
+                                                        return Ok(Some((value, receiver)));
+                                                    }
+                                                }
+                                            }
+                                            RetransmitMessage::TooOld { requester, layer, sequence } => {
+                                                if requester == me_id {
+                                                    let from = from.to_string();
+                                                    let buffer_key = format!("{from}#{}", layer.as_str());
+                                                    jitter_buffers.lock().unwrap().entry(buffer_key).or_default().mark_lost(sequence);
+                                                    let js_event = WasmStreamEvent::ChunkLost {
+                                                        from,
+                                                        sequence,
+                                                        layer: layer.as_str().to_string(),
+                                                    };
+                                                    let value = serde_wasm_bindgen::to_value(&js_event).unwrap();
+                                                    return Ok(Some((value, receiver)));
+                                                }
+                                            }
+                                        }
+                                    }
+                                    continue;
+                                }
                                 tracing::info!("[WASM] Signal from {} size={}", from, data.len());
                                 let js_event = WasmStreamEvent::Signal {
                                     from: from.to_string(),
@@ -208,18 +495,68 @@ impl Stream {
 
         let js_receiver = ReadableStream::from_stream(receiver_stream).into_raw();
 
+        // Second ReadableStream emitting a stats snapshot on a fixed interval,
+        // mirroring the try_unfold pattern used for the media event stream.
+        const STATS_INTERVAL_MS: u32 = 1_000;
+        let stats_source = n0_future::stream::unfold((), {
+            let neighbors = neighbors2.clone();
+            let stats = stats.clone();
+            let clock_sync = clock_sync.clone();
+            let shutdown = shutdown.clone();
+            move |()| {
+                let neighbors = neighbors.clone();
+                let stats = stats.clone();
+                let clock_sync = clock_sync.clone();
+                let shutdown = shutdown.clone();
+                async move {
+                    gloo_timers::future::TimeoutFuture::new(STATS_INTERVAL_MS).await;
+                    if shutdown.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    let neighbor_count = neighbors.lock().unwrap().len();
+                    let snapshot = stats.lock().unwrap().snapshot(neighbor_count, &clock_sync.lock().unwrap());
+                    let value = serde_wasm_bindgen::to_value(&snapshot).unwrap();
+                    Some((value, ()))
+                }
+            }
+        });
+        let stats_stream = ReadableStream::from_stream(stats_source).into_raw();
+
         Self {
             topic_id,
             me: me.to_string(),
             bootstrap,
             neighbors,
-            sender: StreamSender(sender),
+            sender: StreamSender {
+                inner: sender,
+                congestion_enabled,
+                stats: stats.clone(),
+                retransmit_enabled,
+                retransmit_server,
+            },
             receiver: js_receiver,
             ticket,
+            jitter_buffers,
+            jitter_config,
+            clock_sync,
+            stats,
+            stats_stream,
+            selected_layer,
+            available_layers,
+            shutdown,
         }
     }
 }
 
+impl Drop for Stream {
+    /// Stop the feedback/jitter-tick/clocksync-probe/NACK loops and let the
+    /// stats `ReadableStream` end, so dropping this `Stream` (e.g. when JS
+    /// garbage-collects it) doesn't leak background timers forever.
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
 #[wasm_bindgen]
 impl Stream {
     #[wasm_bindgen(getter)]
@@ -264,42 +601,484 @@ impl Stream {
     pub fn neighbors(&self) -> Vec<String> {
         self.neighbors.lock().unwrap().iter().cloned().collect()
     }
+
+    /// Configure the per-source jitter buffer: `{ target_delay_ms, max_depth }`.
+    pub fn set_jitter_buffer(&self, opts: JsValue) -> Result<(), JsError> {
+        let opts: JitterBufferOpts = serde_wasm_bindgen::from_value(opts)?;
+        *self.jitter_config.lock().unwrap() = JitterBufferConfig {
+            target_delay_ms: opts.target_delay_ms,
+            max_depth: opts.max_depth,
+        };
+        Ok(())
+    }
+
+    /// Select which simulcast layer to receive. `MediaChunk` events are
+    /// filtered to this layer; if it hasn't been seen from the broadcaster
+    /// yet, the nearest layer seen so far is forwarded instead until it shows up.
+    pub fn select_layer(&self, quality: String) {
+        *self.selected_layer.lock().unwrap() = StreamQuality::parse(&quality);
+    }
+
+    /// Layers observed so far from the broadcaster(s) on this topic. A layer
+    /// stays listed once seen, even if its chunks stop arriving.
+    pub fn available_layers(&self) -> Vec<String> {
+        self.available_layers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|q| q.as_str().to_string())
+            .collect()
+    }
+
+    /// Estimated clock offset (ms) to `endpoint_id`, such that
+    /// `offset ≈ remote_time − local_time`. `0.0` if no samples yet.
+    pub fn neighbor_clock_offset(&self, endpoint_id: String) -> f64 {
+        self.clock_sync.lock().unwrap().offset(&endpoint_id)
+    }
+
+    /// Convert a remote `MediaChunk.timestamp` from `endpoint_id` into a
+    /// local playout deadline (ms), combining the estimated clock offset
+    /// with the configured jitter-buffer delay.
+    pub fn playout_deadline(&self, endpoint_id: String, timestamp: u64) -> f64 {
+        let offset = self.clock_sync.lock().unwrap().offset(&endpoint_id);
+        let target_delay_ms = self.jitter_config.lock().unwrap().target_delay_ms;
+        crate::clocksync::playout_deadline(timestamp, offset, target_delay_ms) as f64
+    }
+
+    /// Snapshot of current stream stats as JSON: aggregate send bitrate,
+    /// per-neighbor receive bitrate, loss, jitter and RTT, neighbor count
+    /// and churn, and current jitter-buffer occupancy. Receive-side figures
+    /// are reported per neighbor only; they are not summed across neighbors.
+    pub fn stats(&self) -> JsValue {
+        let neighbor_count = self.neighbors.lock().unwrap().len();
+        let snapshot = self
+            .stats
+            .lock()
+            .unwrap()
+            .snapshot(neighbor_count, &self.clock_sync.lock().unwrap());
+        let jitter_buffer: HashMap<String, usize> = self
+            .jitter_buffers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(source, buffer)| (source.clone(), buffer.occupancy()))
+            .collect();
+        let report = StatsReport { stats: &snapshot, jitter_buffer };
+        serde_wasm_bindgen::to_value(&report).unwrap_or(JsValue::NULL)
+    }
+
+    /// A second `ReadableStream` that emits a stats snapshot on a fixed
+    /// interval. Unlike `stats()`, it does not include jitter-buffer occupancy.
+    #[wasm_bindgen(getter)]
+    pub fn stats_stream(&mut self) -> StreamReceiver {
+        self.stats_stream.clone()
+    }
+}
+
+/// Combines the periodic `StatsSnapshot` with point-in-time jitter buffer occupancy.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatsReport<'a> {
+    #[serde(flatten)]
+    stats: &'a StatsSnapshot,
+    jitter_buffer: HashMap<String, usize>,
 }
 
 /// WASM wrapper for StreamSender
 #[wasm_bindgen]
 #[derive(Clone)]
-pub struct StreamSender(node::StreamSender);
+pub struct StreamSender {
+    inner: node::StreamSender,
+    congestion_enabled: Arc<AtomicBool>,
+    stats: Arc<Mutex<StatsCollector>>,
+    retransmit_enabled: Arc<AtomicBool>,
+    retransmit_server: Arc<Mutex<RetransmitServer>>,
+}
 
 #[wasm_bindgen]
 impl StreamSender {
-    /// Broadcast a media chunk
-    pub async fn broadcast_chunk(&self, data: Uint8Array, sequence: u32) -> Result<(), JsError> {
+    /// Broadcast a media chunk on simulcast layer `layer` (`"low"`, `"medium"`, `"high"`, `"ultra"`).
+    /// Sequence numbers are independent per layer.
+    pub async fn broadcast_layer_chunk(
+        &self,
+        data: Uint8Array,
+        sequence: u32,
+        layer: String,
+    ) -> Result<(), JsError> {
         let data_vec = uint8array_to_vec(&data);
-        console::log_1(&format!("[WASM] Broadcasting chunk {} ({} bytes)", sequence, data_vec.len()).into());
-        self.0.broadcast_chunk(data_vec, sequence as u64).await.map_err(to_js_err)?;
+        let layer = StreamQuality::parse(&layer);
+        console::log_1(&format!("[WASM] Broadcasting chunk {} layer={} ({} bytes)", sequence, layer.as_str(), data_vec.len()).into());
+        self.stats.lock().unwrap().record_sent_chunk(data_vec.len());
+        self.retransmit_server
+            .lock()
+            .unwrap()
+            .record(layer, sequence as u64, node::now_millis(), data_vec.clone());
+        self.inner
+            .broadcast_layer_chunk(data_vec, sequence as u64, layer)
+            .await
+            .map_err(to_js_err)?;
         console::log_1(&format!("[WASM] Chunk {} broadcast complete", sequence).into());
         Ok(())
     }
 
     /// Send presence announcement
     pub async fn send_presence(&self) -> Result<(), JsError> {
-        self.0.send_presence().await.map_err(to_js_err)
+        self.inner.send_presence().await.map_err(to_js_err)
     }
 
     /// Send signaling payload
     pub async fn send_signal(&self, data: Uint8Array) -> Result<(), JsError> {
         let data_vec = uint8array_to_vec(&data);
         console::log_1(&format!("[WASM] Sending signal ({} bytes)", data_vec.len()).into());
-        self.0.send_signal(data_vec).await.map_err(to_js_err)
+        self.inner.send_signal(data_vec).await.map_err(to_js_err)
     }
 
     /// Set the broadcaster name
     pub fn set_name(&self, name: String) {
-        self.0.set_name(name);
+        self.inner.set_name(name);
+    }
+
+    /// Enable or disable receiver-driven congestion control for this stream.
+    ///
+    /// When enabled, viewers periodically report loss/delay feedback over the
+    /// signaling channel, and the peer the feedback is about runs an AIMD
+    /// loop to pick a target `StreamQuality`, surfaced as `QualityChange`.
+    pub fn enable_congestion_control(&self, enabled: bool) {
+        self.congestion_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Enable or disable NACK-based retransmission for this stream.
+    ///
+    /// When enabled, viewers ask for recently dropped sequences over the
+    /// signaling channel, and the peer named as the chunk's source replays it
+    /// from its short retransmit buffer (or reports it as aged out).
+    pub fn enable_retransmission(&self, enabled: bool) {
+        self.retransmit_enabled.store(enabled, Ordering::Relaxed);
     }
 }
 
+/// Spawn the periodic (every 500ms) task that turns this stream's receive
+/// windows into congestion feedback and broadcasts it over `send_signal`.
+/// Exits once `shutdown` is set, so the task doesn't outlive its `Stream`.
+fn spawn_feedback_loop(
+    sender: node::StreamSender,
+    enabled: Arc<AtomicBool>,
+    windows: Arc<Mutex<HashMap<String, ReceiveWindow>>>,
+    shutdown: Arc<AtomicBool>,
+) {
+    wasm_bindgen_futures::spawn_local(async move {
+        loop {
+            gloo_timers::future::TimeoutFuture::new(500).await;
+            if shutdown.load(Ordering::Relaxed) {
+                return;
+            }
+            if !enabled.load(Ordering::Relaxed) {
+                continue;
+            }
+            let feedback: Vec<(String, FeedbackSample)> = {
+                let windows = windows.lock().unwrap();
+                windows
+                    .iter()
+                    .filter_map(|(key, window)| {
+                        let (source, _layer) = split_buffer_key(key);
+                        window.feedback().map(|fb| (source.to_string(), fb))
+                    })
+                    .collect()
+            };
+            for (source, sample) in feedback {
+                if let Err(e) = sender.send_signal(encode_feedback(&source, sample)).await {
+                    tracing::warn!("[WASM] Failed to send congestion feedback: {:?}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Wakes the `receiver_stream` consumer when something pushes a value into
+/// `pending_out` from outside the consumer's own poll loop (currently just
+/// the jitter tick loop below). Without this, a future parked on
+/// `receiver.next().await` with no further gossip traffic would leave
+/// queued entries stuck until unrelated traffic happened to arrive.
+#[derive(Clone, Default)]
+struct WakeSignal(Arc<Mutex<WakeState>>);
+
+#[derive(Default)]
+struct WakeState {
+    pending: bool,
+    waker: Option<Waker>,
+}
+
+impl WakeSignal {
+    fn wake(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.pending = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll_notified(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.0.lock().unwrap();
+        if state.pending {
+            state.pending = false;
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Result of racing `receiver.next()` against a [`WakeSignal`].
+enum NextOrWoken<T> {
+    Event(T),
+    Woken,
+}
+
+/// Await the next stream item, but also resolve early (as `Woken`) if
+/// `wake` fires first, so the consumer re-checks `pending_out` promptly
+/// instead of staying parked until the next gossip event.
+async fn next_or_woken(
+    receiver: &mut n0_future::boxed::BoxStream<Result<StreamEvent, anyhow::Error>>,
+    wake: &WakeSignal,
+) -> NextOrWoken<Option<Result<StreamEvent, anyhow::Error>>> {
+    let mut next = receiver.next();
+    poll_fn(move |cx| {
+        if let Poll::Ready(v) = Pin::new(&mut next).poll(cx) {
+            return Poll::Ready(NextOrWoken::Event(v));
+        }
+        match wake.poll_notified(cx) {
+            Poll::Ready(()) => Poll::Ready(NextOrWoken::Woken),
+            Poll::Pending => Poll::Pending,
+        }
+    })
+    .await
+}
+
+/// Re-check every source's jitter buffer on a fixed interval so a gap is
+/// still declared lost (and reordering still completes) even if no further
+/// chunks arrive from that source to trigger the check opportunistically.
+/// Exits once `shutdown` is set, so the task doesn't outlive its `Stream`.
+fn spawn_jitter_tick_loop(
+    buffers: Arc<Mutex<HashMap<String, JitterBuffer>>>,
+    config: Arc<Mutex<JitterBufferConfig>>,
+    pending_out: Arc<Mutex<VecDeque<JsValue>>>,
+    wake: WakeSignal,
+    shutdown: Arc<AtomicBool>,
+) {
+    const TICK_MS: u32 = 20;
+    wasm_bindgen_futures::spawn_local(async move {
+        loop {
+            gloo_timers::future::TimeoutFuture::new(TICK_MS).await;
+            if shutdown.load(Ordering::Relaxed) {
+                return;
+            }
+            let config = *config.lock().unwrap();
+            let mut out = VecDeque::new();
+            {
+                let mut buffers = buffers.lock().unwrap();
+                for (key, buffer) in buffers.iter_mut() {
+                    let (from, layer) = split_buffer_key(key);
+                    out.extend(jitter_events_to_js(from, layer, buffer.poll(&config)));
+                }
+            }
+            if !out.is_empty() {
+                pending_out.lock().unwrap().extend(out);
+                wake.wake();
+            }
+        }
+    });
+}
+
+/// Re-check every source's jitter buffer for gaps that haven't yet aged into
+/// a `ChunkLost`, and ask the source to resend them over `send_signal`.
+/// Exits once `shutdown` is set, so the task doesn't outlive its `Stream`.
+fn spawn_nack_loop(
+    sender: node::StreamSender,
+    enabled: Arc<AtomicBool>,
+    buffers: Arc<Mutex<HashMap<String, JitterBuffer>>>,
+    me_id: String,
+    shutdown: Arc<AtomicBool>,
+) {
+    const NACK_INTERVAL_MS: u32 = 200;
+    wasm_bindgen_futures::spawn_local(async move {
+        loop {
+            gloo_timers::future::TimeoutFuture::new(NACK_INTERVAL_MS).await;
+            if shutdown.load(Ordering::Relaxed) {
+                return;
+            }
+            if !enabled.load(Ordering::Relaxed) {
+                continue;
+            }
+            let gaps: Vec<(String, StreamQuality, Vec<u64>)> = {
+                buffers
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter_map(|(key, buffer)| {
+                        let missing_sequences = buffer.pending_gaps();
+                        if missing_sequences.is_empty() {
+                            return None;
+                        }
+                        let (from, layer) = split_buffer_key(key);
+                        Some((from.to_string(), layer, missing_sequences))
+                    })
+                    .collect()
+            };
+            for (from, layer, missing_sequences) in gaps {
+                let nack = RetransmitMessage::Nack {
+                    from,
+                    requester: me_id.clone(),
+                    layer,
+                    missing_sequences,
+                };
+                if let Err(e) = sender.send_signal(encode_retransmit(&nack)).await {
+                    tracing::warn!("[WASM] Failed to send NACK: {:?}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Split a `jitter_buffers` key of the form `"{from}#{layer}"` back into its parts.
+fn split_buffer_key(key: &str) -> (&str, StreamQuality) {
+    match key.rsplit_once('#') {
+        Some((from, layer)) => (from, StreamQuality::parse(layer)),
+        None => (key, StreamQuality::Medium),
+    }
+}
+
+/// Convert jitter buffer output into serialized JS events, in order.
+fn jitter_events_to_js(from: &str, layer: StreamQuality, events: Vec<JitterEvent>) -> VecDeque<JsValue> {
+    events
+        .into_iter()
+        .map(|event| {
+            let js_event = match event {
+                JitterEvent::Ready { data, sequence, timestamp } => WasmStreamEvent::MediaChunk {
+                    from: from.to_string(),
+                    data,
+                    sequence,
+                    timestamp,
+                    layer: layer.as_str().to_string(),
+                },
+                JitterEvent::Lost { sequence } => WasmStreamEvent::ChunkLost {
+                    from: from.to_string(),
+                    sequence,
+                    layer: layer.as_str().to_string(),
+                },
+            };
+            serde_wasm_bindgen::to_value(&js_event).unwrap()
+        })
+        .collect()
+}
+
+/// Pick the layer to actually forward: `desired` if currently available,
+/// otherwise whichever known layer is closest to it in the quality tiers.
+fn nearest_available_layer(
+    desired: StreamQuality,
+    available: &BTreeSet<StreamQuality>,
+) -> Option<StreamQuality> {
+    if available.contains(&desired) {
+        return Some(desired);
+    }
+    let desired_rank = QUALITY_TIERS.iter().position(|q| *q == desired).unwrap_or(0);
+    available.iter().copied().min_by_key(|q| {
+        let rank = QUALITY_TIERS.iter().position(|t| t == q).unwrap_or(0);
+        rank.abs_diff(desired_rank)
+    })
+}
+
+/// Options accepted by `Stream::set_jitter_buffer`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct JitterBufferOpts {
+    target_delay_ms: u64,
+    max_depth: usize,
+}
+
+/// Periodically probe the topic for clock-sync offset/RTT samples. Exits
+/// once `shutdown` is set, so the task doesn't outlive its `Stream`.
+fn spawn_clocksync_probe_loop(sender: node::StreamSender, me_id: String, shutdown: Arc<AtomicBool>) {
+    const PROBE_INTERVAL_MS: u32 = 5_000;
+    wasm_bindgen_futures::spawn_local(async move {
+        loop {
+            gloo_timers::future::TimeoutFuture::new(PROBE_INTERVAL_MS).await;
+            if shutdown.load(Ordering::Relaxed) {
+                return;
+            }
+            let probe = ClockSyncMessage::Probe {
+                requester: me_id.clone(),
+                t1: node::now_millis(),
+            };
+            if let Err(e) = sender.send_signal(encode_clocksync(&probe)).await {
+                tracing::warn!("[WASM] Failed to send clock-sync probe: {:?}", e);
+            }
+        }
+    });
+}
+
+/// Tag byte marking a `send_signal` payload as a clock-sync probe/response.
+const CLOCKSYNC_MAGIC: u8 = 0xC8;
+
+fn encode_clocksync(msg: &ClockSyncMessage) -> Vec<u8> {
+    let mut bytes = vec![CLOCKSYNC_MAGIC];
+    bytes.extend(postcard::to_stdvec(msg).expect("ClockSyncMessage is serializable"));
+    bytes
+}
+
+fn decode_clocksync(data: &[u8]) -> Option<ClockSyncMessage> {
+    let (&magic, rest) = data.split_first()?;
+    if magic != CLOCKSYNC_MAGIC {
+        return None;
+    }
+    postcard::from_bytes(rest).ok()
+}
+
+/// Tag byte marking a `send_signal` payload as congestion feedback rather
+/// than an app-level signaling message (e.g. WebRTC SDP/ICE).
+const FEEDBACK_MAGIC: u8 = 0xC7;
+
+#[derive(Serialize, Deserialize)]
+struct FeedbackEnvelope {
+    source: String,
+    sample: FeedbackSample,
+}
+
+fn encode_feedback(source: &str, sample: FeedbackSample) -> Vec<u8> {
+    let envelope = FeedbackEnvelope {
+        source: source.to_string(),
+        sample,
+    };
+    let mut bytes = vec![FEEDBACK_MAGIC];
+    bytes.extend(postcard::to_stdvec(&envelope).expect("FeedbackEnvelope is serializable"));
+    bytes
+}
+
+fn decode_feedback(data: &[u8]) -> Option<(String, FeedbackSample)> {
+    let (&magic, rest) = data.split_first()?;
+    if magic != FEEDBACK_MAGIC {
+        return None;
+    }
+    let envelope: FeedbackEnvelope = postcard::from_bytes(rest).ok()?;
+    Some((envelope.source, envelope.sample))
+}
+
+/// Tag byte marking a `send_signal` payload as a NACK/resend/too-old message.
+const RETRANSMIT_MAGIC: u8 = 0xC9;
+
+fn encode_retransmit(msg: &RetransmitMessage) -> Vec<u8> {
+    let mut bytes = vec![RETRANSMIT_MAGIC];
+    bytes.extend(postcard::to_stdvec(msg).expect("RetransmitMessage is serializable"));
+    bytes
+}
+
+fn decode_retransmit(data: &[u8]) -> Option<RetransmitMessage> {
+    let (&magic, rest) = data.split_first()?;
+    if magic != RETRANSMIT_MAGIC {
+        return None;
+    }
+    postcard::from_bytes(rest).ok()
+}
+
 /// Stream events for JS
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -316,6 +1095,7 @@ pub enum WasmStreamEvent {
         data: Vec<u8>,
         sequence: u64,
         timestamp: u64,
+        layer: String,
     },
     Signal {
         from: String,
@@ -323,6 +1103,14 @@ pub enum WasmStreamEvent {
         timestamp: u64,
     },
     Lagged,
+    /// Emitted when receiver-driven congestion control changes the target quality.
+    QualityChange { quality: String },
+    /// Emitted when the jitter buffer gives up waiting on a missing sequence.
+    ChunkLost {
+        from: String,
+        sequence: u64,
+        layer: String,
+    },
 }
 
 /// Ticket options